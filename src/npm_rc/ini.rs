@@ -42,15 +42,76 @@ pub enum Value<'a> {
 pub fn parse_ini(
   input: &str,
 ) -> Result<Vec<KeyValueOrSection>, ParseErrorFailureError> {
-  with_failure_handling(|input| {
-    let (input, _) = skip_trivia(input)?;
-    let (input, items) = many0(|input| {
-      let (input, kv_or_section) = parse_kv_or_section(input)?;
-      let (input, _) = skip_trivia(input)?;
-      Ok((input, kv_or_section))
-    })(input)?;
-    Ok((input, items))
-  })(input)
+  iter_ini(input).collect()
+}
+
+/// Lazily parses the ini file one key value or section at a time.
+pub fn iter_ini(
+  input: &str,
+) -> impl Iterator<Item = Result<KeyValueOrSection, ParseErrorFailureError>> {
+  iter_items(input, parse_kv_or_section)
+}
+
+/// Parses the ini file along with the 1-based line number that each key
+/// value starts on. Sections are split up so that each has a single item.
+pub fn parse_ini_with_lines(
+  input: &str,
+) -> Result<Vec<(usize, KeyValueOrSection)>, ParseErrorFailureError> {
+  fn parse_located_key_value(input: &str) -> ParseResult<(&str, KeyValue)> {
+    let (rest, kv) = parse_key_value(input)?;
+    Ok((rest, (input, kv)))
+  }
+
+  let line_of =
+    |start: &str| input[..input.len() - start.len()].matches('\n').count() + 1;
+  let items = iter_items(
+    input,
+    or(
+      map(
+        parse_section_with(parse_located_key_value),
+        |(header, items)| (Some(header), items),
+      ),
+      map(parse_located_key_value, |item| (None, vec![item])),
+    ),
+  );
+  let mut result = Vec::new();
+  for item in items {
+    let (header, items) = item?;
+    for (start, kv) in items {
+      let kv_or_section = match header {
+        Some(header) => KeyValueOrSection::Section(Section {
+          header,
+          items: vec![kv],
+        }),
+        None => KeyValueOrSection::KeyValue(kv),
+      };
+      result.push((line_of(start), kv_or_section));
+    }
+  }
+  Ok(result)
+}
+
+fn iter_items<'a, T: 'a>(
+  input: &'a str,
+  parse_item: impl Fn(&'a str) -> ParseResult<'a, T> + 'a,
+) -> impl Iterator<Item = Result<T, ParseErrorFailureError>> + 'a {
+  let mut remaining = Some(input);
+  std::iter::from_fn(move || {
+    let (input, _) = skip_trivia(remaining.take()?).ok()?;
+    if input.is_empty() {
+      return None;
+    }
+    match parse_item(input) {
+      Ok((next_input, item)) if next_input.len() < input.len() => {
+        remaining = Some(next_input);
+        Some(Ok(item))
+      }
+      Ok(_) | Err(ParseError::Backtrace) => {
+        Some(ParseErrorFailure::new_for_trailing_input(input).into_result())
+      }
+      Err(ParseError::Failure(e)) => Some(e.into_result()),
+    }
+  })
 }
 
 fn parse_kv_or_section(input: &str) -> ParseResult<KeyValueOrSection> {
@@ -61,16 +122,30 @@ fn parse_kv_or_section(input: &str) -> ParseResult<KeyValueOrSection> {
 }
 
 fn parse_section(input: &str) -> ParseResult<Section> {
-  let (input, _) = skip_non_newline_whitespace(input)?;
-  let (input, header) = parse_section_header(input)?;
-  let (input, _) = skip_non_newline_whitespace(input)?;
-  let (input, _) = skip_trivia(input)?;
-  let (input, items) = many0(|input| {
-    let (input, kv) = parse_key_value(input)?;
+  map(parse_section_with(parse_key_value), |(header, items)| {
+    Section { header, items }
+  })(input)
+}
+
+fn parse_section_with<'a, T>(
+  parse_item: impl Fn(&'a str) -> ParseResult<'a, T>,
+) -> impl Fn(&'a str) -> ParseResult<'a, (&'a str, Vec<T>)> {
+  move |input| {
+    let (input, _) = skip_non_newline_whitespace(input)?;
+    let (input, header) = parse_section_header(input)?;
+    let (input, _) = skip_non_newline_whitespace(input)?;
     let (input, _) = skip_trivia(input)?;
-    Ok((input, kv))
-  })(input)?;
-  Ok((input, Section { header, items }))
+    let (input, items) = many0(|input| {
+      // the start of the next section
+      if input.starts_with('[') {
+        return ParseError::backtrace();
+      }
+      let (input, item) = parse_item(input)?;
+      let (input, _) = skip_trivia(input)?;
+      Ok((input, item))
+    })(input)?;
+    Ok((input, (header, items)))
+  }
 }
 
 fn parse_section_header(input: &str) -> ParseResult<&str> {
@@ -95,10 +170,11 @@ fn parse_key_value(input: &str) -> ParseResult<KeyValue> {
 
   let (input, key) = parse_key(input)?;
   let (input, _) = skip_non_newline_whitespace(input)?;
+  let allow_continuation = is_multiline_key(&key);
   let (input, value) = or(
     |input| {
       let (input, _) = ch('=')(input)?;
-      parse_value(input)
+      parse_value(input, allow_continuation)
     },
     map(parse_empty_value, |_| Value::Boolean(true)),
   )(input)?;
@@ -122,9 +198,19 @@ fn parse_key(input: &str) -> ParseResult<Key> {
   )(input)
 }
 
-fn parse_value(input: &str) -> ParseResult<Value> {
-  fn parse_unquoted(input: &str) -> ParseResult<Value> {
-    let (input, value) = take_until_comment_or_newline(input)?;
+/// Gets if the key holds a certificate, which are the only values
+/// that may continue on the next line (ex. `cache=C:\npm\` must not).
+fn is_multiline_key(key: &Key) -> bool {
+  let (Key::Plain(key) | Key::Array(key)) = key;
+  // ex. //example.com/:cert
+  let name = key.rsplit_once(':').map(|(_, name)| name).unwrap_or(key);
+  matches!(name, "ca" | "cert" | "key")
+}
+
+fn parse_value(input: &str, allow_continuation: bool) -> ParseResult<Value> {
+  let parse_unquoted = |input| -> ParseResult<Value> {
+    let (input, value) =
+      take_until_comment_or_newline(input, allow_continuation)?;
     let value = trim_cow_str(value);
     Ok((
       input,
@@ -142,7 +228,7 @@ fn parse_value(input: &str) -> ParseResult<Value> {
         }
       },
     ))
-  }
+  };
 
   or(
     map(parse_quoted_skipping_spaces, Value::String),
@@ -249,8 +335,25 @@ fn skip_comment(input: &str) -> ParseResult<()> {
   Ok((input, ()))
 }
 
-fn take_until_comment_or_newline(input: &str) -> ParseResult<Cow<str>> {
-  take_while_not_comment_and(|c| c != '\n')(input)
+fn take_until_comment_or_newline(
+  input: &str,
+  allow_continuation: bool,
+) -> ParseResult<Cow<str>> {
+  let (rest, text) = take_while_not_comment_and(|c| c != '\n')(input)?;
+  if !allow_continuation {
+    return Ok((rest, text));
+  }
+  // a line ending in an unescaped backslash continues on the next line
+  let line = input[..input.len() - rest.len()].trim_end_matches('\r');
+  let backslash_count = line.len() - line.trim_end_matches('\\').len();
+  if backslash_count % 2 == 1 && rest.starts_with('\n') {
+    let text = text.trim_end_matches('\r');
+    let text = &text[..text.len() - 1];
+    let (rest, next_text) = take_until_comment_or_newline(&rest[1..], true)?;
+    Ok((rest, Cow::Owned(format!("{}{}", text, next_text))))
+  } else {
+    Ok((rest, text))
+  }
 }
 
 fn take_while_not_comment_and<'a>(
@@ -383,4 +486,164 @@ a = 1
       ]
     )
   }
+
+  #[test]
+  fn parses_multiple_sections() {
+    let ini = parse_ini(
+      r#"
+[a]
+b = 1
+[c]
+d = 2
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+      ini,
+      vec![
+        KeyValueOrSection::Section(Section {
+          header: "a",
+          items: vec![KeyValue {
+            key: Key::Plain("b".into()),
+            value: Value::Number(1),
+          }]
+        }),
+        KeyValueOrSection::Section(Section {
+          header: "c",
+          items: vec![KeyValue {
+            key: Key::Plain("d".into()),
+            value: Value::Number(2),
+          }]
+        }),
+      ]
+    )
+  }
+
+  #[test]
+  fn parses_continuation_lines() {
+    let ini = parse_ini(
+      "ca=-----BEGIN CERTIFICATE-----\\\nMIIBdata\\\r\n-----END CERTIFICATE-----\npath=C:\\\\dir\\\\\nother=1\n",
+    )
+    .unwrap();
+    assert_eq!(
+      ini,
+      vec![
+        KeyValueOrSection::KeyValue(KeyValue {
+          key: Key::Plain("ca".into()),
+          value: Value::String(
+            "-----BEGIN CERTIFICATE-----MIIBdata-----END CERTIFICATE-----"
+              .into()
+          ),
+        }),
+        // an escaped backslash doesn't continue the line
+        KeyValueOrSection::KeyValue(KeyValue {
+          key: Key::Plain("path".into()),
+          value: Value::String("C:\\dir\\".into()),
+        }),
+        KeyValueOrSection::KeyValue(KeyValue {
+          key: Key::Plain("other".into()),
+          value: Value::Number(1),
+        }),
+      ]
+    );
+  }
+
+  #[test]
+  fn only_continues_certificate_lines() {
+    let ini =
+      parse_ini("cache=C:\\npm\\\nregistry=https://example.com/\n").unwrap();
+    assert_eq!(
+      ini,
+      vec![
+        KeyValueOrSection::KeyValue(KeyValue {
+          key: Key::Plain("cache".into()),
+          value: Value::String("C:\\npm\\".into()),
+        }),
+        KeyValueOrSection::KeyValue(KeyValue {
+          key: Key::Plain("registry".into()),
+          value: Value::String("https://example.com/".into()),
+        }),
+      ]
+    );
+    let ini = parse_ini("//example.com/:cert=BEGIN\\\nEND\n").unwrap();
+    assert_eq!(
+      ini,
+      vec![KeyValueOrSection::KeyValue(KeyValue {
+        key: Key::Plain("//example.com/:cert".into()),
+        value: Value::String("BEGINEND".into()),
+      })]
+    );
+  }
+
+  #[test]
+  fn parses_without_trailing_newline() {
+    let ini = parse_ini("registry=https://x/").unwrap();
+    assert_eq!(
+      ini,
+      vec![KeyValueOrSection::KeyValue(KeyValue {
+        key: Key::Plain("registry".into()),
+        value: Value::String("https://x/".into()),
+      })]
+    );
+    let ini = parse_ini("[a]\r\nb=1").unwrap();
+    assert_eq!(
+      ini,
+      vec![KeyValueOrSection::Section(Section {
+        header: "a",
+        items: vec![KeyValue {
+          key: Key::Plain("b".into()),
+          value: Value::Number(1),
+        }]
+      })]
+    );
+  }
+
+  #[test]
+  fn parses_with_lines() {
+    let ini =
+      parse_ini_with_lines("a=1\n\n; comment\n[b]\nc=2\n\n  d = 3\n[e]\nf=4")
+        .unwrap();
+    assert_eq!(
+      ini,
+      vec![
+        (
+          1,
+          KeyValueOrSection::KeyValue(KeyValue {
+            key: Key::Plain("a".into()),
+            value: Value::Number(1),
+          })
+        ),
+        (
+          5,
+          KeyValueOrSection::Section(Section {
+            header: "b",
+            items: vec![KeyValue {
+              key: Key::Plain("c".into()),
+              value: Value::Number(2),
+            }]
+          })
+        ),
+        (
+          7,
+          KeyValueOrSection::Section(Section {
+            header: "b",
+            items: vec![KeyValue {
+              key: Key::Plain("d".into()),
+              value: Value::Number(3),
+            }]
+          })
+        ),
+        (
+          9,
+          KeyValueOrSection::Section(Section {
+            header: "e",
+            items: vec![KeyValue {
+              key: Key::Plain("f".into()),
+              value: Value::Number(4),
+            }]
+          })
+        ),
+      ]
+    );
+  }
 }
@@ -0,0 +1,77 @@
+// Copyright 2018-2024 the Deno authors. MIT license.
+
+use std::ops::Deref;
+
+/// A credential that isn't shown in the `Debug` output and, with the
+/// `zeroize` feature, is wiped from memory when it's dropped.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct SecretString(String);
+
+impl SecretString {
+  pub fn new(value: String) -> Self {
+    Self(value)
+  }
+
+  /// Gets the value of the secret, which shouldn't be logged.
+  pub fn expose_secret(&self) -> &str {
+    &self.0
+  }
+}
+
+impl Deref for SecretString {
+  type Target = str;
+
+  fn deref(&self) -> &str {
+    &self.0
+  }
+}
+
+impl From<String> for SecretString {
+  fn from(value: String) -> Self {
+    Self(value)
+  }
+}
+
+impl From<&str> for SecretString {
+  fn from(value: &str) -> Self {
+    Self(value.to_string())
+  }
+}
+
+impl std::fmt::Debug for SecretString {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str("SecretString(***)")
+  }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretString {
+  fn drop(&mut self) {
+    use zeroize::Zeroize;
+
+    self.0.zeroize();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn debug_hides_value() {
+    let secret = SecretString::from("my-token");
+    assert_eq!(format!("{:?}", secret), "SecretString(***)");
+    assert_eq!(
+      format!("{:?}", Some(secret.clone())),
+      "Some(SecretString(***))"
+    );
+    assert_eq!(secret.expose_secret(), "my-token");
+    assert_eq!(&*secret, "my-token");
+  }
+
+  #[test]
+  fn compares_values() {
+    assert_eq!(SecretString::from("a"), SecretString::new("a".to_string()));
+    assert_ne!(SecretString::from("a"), SecretString::from("b"));
+  }
+}